@@ -2,23 +2,157 @@ use std::env;
 use std::path::PathBuf;
 
 use bindgen;
+use bindgen::callbacks::{DeriveInfo, EnumVariantValue, ItemInfo, ItemKind, ParseCallbacks};
 use cmake::Config;
 
+/// Small value types that OpenMM passes by value, which should be generated as transparent,
+/// derivable Rust structs rather than opaque blobs
+///
+/// These are kept out of the `opaque_type` patterns in [`do_cpp_bindgen`] (via [`opaque_except`])
+/// so the safe layer can read and construct them field-by-field, and get the derives in
+/// [`OpenMMParseCallbacks::add_derives`].
+const POD_TYPES: &[&str] = &["OpenMM::Vec3", "OpenMM::State"];
+
+/// Rename generated items to idiomatic Rust, and attach derives to the curated [`POD_TYPES`]
+///
+/// OpenMM's C API spells every symbol as `OpenMM_<Type>` and every enum variant as
+/// `OpenMM_<Type>_<Variant>`. This turns `OpenMM_NonbondedForce_NonbondedMethod::
+/// OpenMM_NonbondedForce_CutoffPeriodic` into the much more idiomatic
+/// `NonbondedForce_NonbondedMethod::CutoffPeriodic`.
+///
+/// Only type and enum names are stripped of their `OpenMM_` prefix: function and variable
+/// symbols are left exactly as OpenMM spells them, since the safe wrapper crates call them by
+/// their full C name (`OpenMM_System_create`, `OpenMM_NonbondedForce_addParticle`, ...).
+#[derive(Debug)]
+struct OpenMMParseCallbacks;
+
+impl ParseCallbacks for OpenMMParseCallbacks {
+    fn enum_variant_name(
+        &self,
+        enum_name: Option<&str>,
+        original_variant_name: &str,
+        _variant_value: EnumVariantValue,
+    ) -> Option<String> {
+        let variant = original_variant_name.strip_prefix("OpenMM_").unwrap_or(original_variant_name);
+        let enum_name = enum_name?.strip_prefix("OpenMM_").unwrap_or(enum_name?);
+        // C variants are prefixed with the type the enum belongs to (`NonbondedForce`), which is
+        // the enum's name without its last `_`-separated segment (`NonbondedMethod`)
+        let owner = enum_name.rsplit_once('_').map_or(enum_name, |(owner, _)| owner);
+
+        Some(
+            variant
+                .strip_prefix(enum_name)
+                .or_else(|| variant.strip_prefix(owner))
+                .unwrap_or(variant)
+                .trim_start_matches('_')
+                .to_owned(),
+        )
+    }
+
+    fn generated_name_override(&self, item_info: ItemInfo<'_>) -> Option<String> {
+        if let Some(pod_type) = POD_TYPES.iter().find(|pod_type| pod_type.ends_with(item_info.name)) {
+            return Some(pod_type.rsplit("::").next().unwrap().to_owned());
+        }
+
+        if item_info.kind == ItemKind::Type {
+            return item_info.name.strip_prefix("OpenMM_").map(str::to_owned);
+        }
+
+        None
+    }
+
+    fn add_derives(&self, info: &DeriveInfo<'_>) -> Vec<String> {
+        if POD_TYPES.iter().any(|pod_type| pod_type.ends_with(info.name)) {
+            vec!["Debug".to_owned(), "Clone".to_owned(), "Copy".to_owned(), "PartialEq".to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Build a regex matching every string *except* those that exactly equal one of `literals`
+///
+/// `Builder::opaque_type` only ever adds to the opaque set — calling it again unions in more
+/// patterns, it never subtracts — and the `regex` crate bindgen matches with has no lookaround,
+/// so "opaque everything except these two classes" can't be written as `(?!Vec3|State).*`.
+/// Instead this builds the same exclusion the long way round: a string can only be one of
+/// `literals` if its length matches one of theirs, so any other length is unconditionally
+/// opaque, and same-length strings are opaque unless they diverge from that literal nowhere at
+/// all (i.e. equal it exactly).
+///
+/// Assumes no two `literals` share a length, which holds for [`POD_TYPES`] today, and that
+/// `literals` contain no regex metacharacters (they're plain `Namespace::Identifier` names).
+fn opaque_except(literals: &[&str]) -> String {
+    let mut lengths: Vec<usize> = literals.iter().map(|literal| literal.len()).collect();
+    lengths.sort_unstable();
+    lengths.dedup();
+
+    let mut branches = Vec::new();
+
+    let mut next_unclaimed = 0;
+    for &len in &lengths {
+        if len > next_unclaimed {
+            branches.push(format!("^.{{{},{}}}$", next_unclaimed, len - 1));
+        }
+        next_unclaimed = len + 1;
+    }
+    branches.push(format!("^.{{{},}}$", next_unclaimed));
+
+    for literal in literals {
+        let len = literal.len();
+        let diverges_at_some_position: Vec<String> =
+            (0..len).map(|i| format!("{}[^{}].{{{}}}", &literal[..i], &literal[i..=i], len - i - 1)).collect();
+        branches.push(format!("^(?:{})$", diverges_at_some_position.join("|")));
+    }
+
+    format!("(?:{})", branches.join("|"))
+}
+
+/// Maps a GPU/accelerated platform Cargo feature to the CMake define that builds its plugin and
+/// the name of the plugin library that define produces
+///
+/// The feature name is given as the suffix of its `CARGO_FEATURE_*` build-script env var, e.g.
+/// `"CUDA"` for a Cargo feature named `cuda`.
+const PLATFORM_PLUGINS: &[(&str, &str, &str)] = &[
+    ("CUDA", "OPENMM_BUILD_CUDA_LIB", "OpenMMCUDA"),
+    ("OPENCL", "OPENMM_BUILD_OPENCL_LIB", "OpenMMOpenCL"),
+    ("CPU_PME", "OPENMM_BUILD_PME_PLUGIN", "OpenMMCPUPME"),
+];
+
 fn cmake_and_build() -> PathBuf {
-    let path = Config::new("external")
+    let mut config = Config::new("external");
+    config
         .cxxflag("-fkeep-inline-functions")
         .define("OPENMM_BUILD_PYTHON_WRAPPERS", "OFF")
         .define("OPENMM_BUILD_C_AND_FORTRAN_WRAPPERS", "ON")
         .define("OPENMM_BUILD_STATIC_LIB", "OFF")
-        .define("OPENMM_BUILD_SHARED_LIB", "ON")
-        .build();
+        .define("OPENMM_BUILD_SHARED_LIB", "ON");
+
+    for (feature, cmake_define, _plugin_lib) in PLATFORM_PLUGINS {
+        config.define(cmake_define, if feature_enabled(feature) { "ON" } else { "OFF" });
+    }
+
+    let path = config.build();
 
     println!("cargo:include={}/include", path.display());
     println!("cargo:lib={}/lib", path.display());
     println!("cargo:rustc-link-search=native={}/lib", path.display());
     println!("cargo:rustc-link-lib=dylib=OpenMM");
 
-    return path;
+    // Plugins are loaded from their own directory at runtime via Platform::loadPluginsFromDirectory,
+    // but they still need to be found by the linker and by anyone reading OPENMM_PLUGIN_DIR back at
+    // runtime, so surface both.
+    let plugin_dir = path.join("lib").join("plugins");
+    println!("cargo:rustc-link-search=native={}", plugin_dir.display());
+    println!("cargo:rustc-env=OPENMM_PLUGIN_DIR={}", plugin_dir.display());
+
+    for (feature, _cmake_define, plugin_lib) in PLATFORM_PLUGINS {
+        if feature_enabled(feature) {
+            println!("cargo:rustc-link-lib=dylib={}", plugin_lib);
+        }
+    }
+
+    path
 }
 
 fn do_cpp_bindgen(include: PathBuf) {
@@ -32,15 +166,20 @@ fn do_cpp_bindgen(include: PathBuf) {
         .clang_arg(format!("-I{}", include.display()))
         .enable_cxx_namespaces()
         .rustfmt_bindings(true)
-        .default_enum_style(bindgen::EnumVariation::ModuleConsts)
+        .default_enum_style(bindgen::EnumVariation::NewType { is_bitfield: false, is_global: false })
         .array_pointers_in_arguments(true)
         .generate_inline_functions(true)
         .whitelist_function("OpenMM::.*")
         .whitelist_var("OpenMM::.*")
         .whitelist_type("OpenMM::.*")
-        .opaque_type(".*")
+        // Every OpenMM class holds non-POD C++ internals (std::string/std::vector members,
+        // impl pointers, ...) that can't be soundly represented as a transparent, field-readable
+        // Rust struct, so they all stay opaque. The curated POD_TYPES value types are the only
+        // exception, carved out by opaque_except() so they're generated as transparent structs.
+        .opaque_type(opaque_except(POD_TYPES))
         .header(header)
         .parse_callbacks(Box::new(bindgen::CargoCallbacks))
+        .parse_callbacks(Box::new(OpenMMParseCallbacks))
         .generate()
         .expect("Unable to generate C++ bindings");
 
@@ -63,10 +202,11 @@ fn do_c_bindgen(include: PathBuf) {
         .clang_arg(format!("-I{}", include.display()))
         .enable_cxx_namespaces()
         .rustfmt_bindings(true)
-        .default_enum_style(bindgen::EnumVariation::ModuleConsts)
+        .default_enum_style(bindgen::EnumVariation::NewType { is_bitfield: false, is_global: false })
         .array_pointers_in_arguments(true)
         .header(header)
         .parse_callbacks(Box::new(bindgen::CargoCallbacks))
+        .parse_callbacks(Box::new(OpenMMParseCallbacks))
         .generate()
         .expect("Unable to generate C bindings");
 
@@ -89,9 +229,23 @@ fn do_c_bindgen(include: PathBuf) {
 //     println!("cargo:rerun-if-changed=external/openmmapi/include/OpenMM.h");
 // }
 
+/// Is the Cargo feature named `feature` enabled for this build
+///
+/// Cargo sets `CARGO_FEATURE_<NAME>` for every enabled feature when running a build script.
+fn feature_enabled(feature: &str) -> bool {
+    env::var(format!("CARGO_FEATURE_{}", feature)).is_ok()
+}
+
 fn main() {
     let path = cmake_and_build();
-    do_cpp_bindgen(path.join("include"));
-    do_c_bindgen(path.join("include"));
+
+    // Only run the (slow, fragile) C++ bindgen pass when it's actually wanted, and skip the C
+    // pass entirely for users who only need the C++ API.
+    if feature_enabled("CPP_API") {
+        do_cpp_bindgen(path.join("include"));
+    }
+    if feature_enabled("C_API") {
+        do_c_bindgen(path.join("include"));
+    }
     // build_cxx()
 }
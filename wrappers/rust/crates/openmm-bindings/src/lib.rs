@@ -1,5 +1,21 @@
 //! Unsafe Rust bindings to the C++ OpenMM library
 //!
+//! # Features
+//!
+//! - `c-api` (default): generate [`c_bindings`] from `OpenMMCWrapper.h`, the C ABI the safe
+//!   `openmm` crate is built on.
+//! - `cpp-api`: generate [`cpp_bindings`] from `OpenMM.h`, the raw C++ API. This bindgen pass is
+//!   much slower and more fragile than `c-api`, so it's opt-in for users who need direct access
+//!   to C++ symbols that aren't exposed through the C wrapper.
+//!
+//! Either feature, both, or neither can be enabled; only the corresponding module and bindgen
+//! pass are compiled.
+//!
+//! In addition, the `cuda`, `opencl`, and `cpu-pme` features each build and link the matching
+//! OpenMM platform plugin. Whichever of these features are enabled, the `OPENMM_PLUGIN_DIR`
+//! environment variable is set at build time to the directory the built plugins live in, for
+//! `Platform` loading at runtime.
+//!
 //! # bindgen
 //!
 //! Bindings are generated automatically by the [bindgen](https://crates.io/crates/bindgen) crate
@@ -13,6 +29,7 @@
 
 // use cxx::{type_id, ExternType};
 
+#[cfg(feature = "c-api")]
 pub mod c_bindings {
     mod bindings {
         #![allow(
@@ -29,6 +46,7 @@ pub mod c_bindings {
     pub use bindings::root::*;
 }
 
+#[cfg(feature = "cpp-api")]
 pub mod cpp_bindings {
     mod bindings {
         #![allow(
@@ -45,9 +63,9 @@ pub mod cpp_bindings {
     pub use bindings::root::OpenMM::*;
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "cpp-api"))]
 mod tests {
-    use super::*;
+    use super::cpp_bindings::*;
 
     #[test]
     fn check_linked() {
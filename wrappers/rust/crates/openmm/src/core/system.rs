@@ -1,10 +1,95 @@
 #[allow(unused_imports)]
 use crate::preface::*;
-use openmm_bindings::c_bindings as openmm;
+use crate::sys as openmm;
 use std::os::raw::c_int;
 use std::ptr::NonNull;
 use std::marker::PhantomData;
 use std::convert::TryFrom;
+use std::fmt;
+
+/// The kind of periodic boundary conditions applied to a [`System`]
+///
+/// OpenMM itself only distinguishes "periodic" from "non-periodic" — see
+/// [`System::uses_periodic_boundary_conditions()`] — and infers periodicity per-`Force`
+/// from how each force's nonbonded method is configured. `PbcType` makes the intended
+/// topology explicit on the `System` itself and is used to validate the default periodic
+/// box vectors whenever they are set, following the same distinction GROMACS draws
+/// between its `PbcType` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PbcType {
+    /// No periodic boundary conditions. Box vectors passed to
+    /// [`System::set_default_periodic_box_vectors()`] are still validated and stored.
+    None,
+    /// Fully periodic in all three dimensions. This is the default.
+    Xyz,
+    /// Periodic in the x and y dimensions only, e.g. a membrane slab with vacuum above
+    /// and below it. The `c` box vector must still be supplied to
+    /// [`System::set_default_periodic_box_vectors()`], but only its length is
+    /// meaningful.
+    Xy,
+}
+
+impl Default for PbcType {
+    fn default() -> Self {
+        Self::Xyz
+    }
+}
+
+impl PbcType {
+    /// Check that `a`, `b`, `c` form a legal, reduced OpenMM periodic box
+    ///
+    /// OpenMM requires the box vectors to be in reduced form: `a` lies along the x axis,
+    /// `b` lies in the xy-plane, and each later vector's component along an earlier vector's
+    /// primary axis is no more than half that earlier vector's length. OpenMM's C++
+    /// `System::setDefaultPeriodicBoxVectors` enforces this unconditionally, regardless of
+    /// whether any `Force` in the `System` actually uses periodic boundary conditions, so
+    /// `self` does not change which of these checks apply — only what the resulting vectors
+    /// mean (e.g. for [`Xy`](Self::Xy), `c`'s direction is ignored but its length must still
+    /// satisfy the same positivity and reduced-form requirements as every other `PbcType`).
+    fn validate(self, a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> Result<(), InvalidBoxVectorsError> {
+        if a[1] != 0.0 || a[2] != 0.0 || b[2] != 0.0 {
+            return Err(InvalidBoxVectorsError::NotReduced);
+        }
+
+        if a[0] <= 0.0 || b[1] <= 0.0 || c[2] <= 0.0 {
+            return Err(InvalidBoxVectorsError::NonPositiveDiagonal);
+        }
+
+        if a[0] < 2.0 * b[0].abs() || a[0] < 2.0 * c[0].abs() || b[1] < 2.0 * c[1].abs() {
+            return Err(InvalidBoxVectorsError::NotTriclinicReduced);
+        }
+
+        Ok(())
+    }
+}
+
+/// The default periodic box vectors passed to [`System::set_default_periodic_box_vectors()`]
+/// do not describe a legal OpenMM periodic cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidBoxVectorsError {
+    /// `a` is not along the x axis, or `b` is not in the xy-plane
+    NotReduced,
+    /// One of the diagonal elements `a[0]`, `b[1]`, or `c[2]` is not positive
+    NonPositiveDiagonal,
+    /// A later vector's component along an earlier vector's axis is more than half that
+    /// earlier vector's length
+    NotTriclinicReduced,
+}
+
+impl fmt::Display for InvalidBoxVectorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotReduced => write!(f, "box vector a must lie along the x axis and b must lie in the xy-plane"),
+            Self::NonPositiveDiagonal => write!(f, "box vectors must have positive diagonal elements"),
+            Self::NotTriclinicReduced => write!(
+                f,
+                "box is not in reduced form: a.x must be at least twice |b.x| and |c.x|, and b.y must be at least twice |c.y|"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidBoxVectorsError {}
 
 /// This type represents a molecular system. The definition of a `System` involves
 /// four elements:
@@ -27,8 +112,9 @@ use std::convert::TryFrom;
 /// [`add_particle()`]: Self::add_particle()
 /// [`add_force()`]: Self::add_force()
 pub struct System {
-    ffi_system: NonNull<openmm::OpenMM_System>,
-    _system_marker: PhantomData<openmm::OpenMM_System>,
+    ffi_system: NonNull<openmm::System>,
+    _system_marker: PhantomData<openmm::System>,
+    pbc_type: PbcType,
 }
 
 impl System {
@@ -38,18 +124,18 @@ impl System {
         let ptr = unsafe { openmm::OpenMM_System_create() };
         let ffi_system = NonNull::new(ptr).expect("OpenMM_System_create returned null pointer");
 
-        Self { ffi_system, _system_marker: PhantomData }
+        Self { ffi_system, _system_marker: PhantomData, pbc_type: PbcType::default() }
     }
 
     /// Get a unique reference to the underlying system
-    fn as_mut(&mut self) -> &mut openmm::OpenMM_System {
+    fn as_mut(&mut self) -> &mut openmm::System {
         // SAFETY: self.ffi_system is a unique non-null pointer to an initialized OpenMM_System,
         // and we are mutably borrowing self
         unsafe { self.ffi_system.as_mut() }
     }
 
     /// Get a shared reference to the underlying system
-    fn as_ref(&self) -> &openmm::OpenMM_System {
+    fn as_ref(&self) -> &openmm::System {
         // SAFETY: self.ffi_system is a unique non-null pointer to an initialized OpenMM_System,
         // and we are immutably borrowing self
         unsafe { self.ffi_system.as_ref() } 
@@ -117,11 +203,87 @@ impl System {
         // a mutable pointer is essential.
         // Note: forces are freed in C++ with the delete operator, so must be allocated
         // in C++ too, with the new operator
-        unsafe { 
-            let force_ptr = force.into_ptr() as *mut openmm::OpenMM_Force;
-            openmm::OpenMM_System_addForce(self.as_mut(), force_ptr) 
+        unsafe {
+            let force_ptr = force.into_ptr() as *mut openmm::Force;
+            openmm::OpenMM_System_addForce(self.as_mut(), force_ptr)
         }
     }
+
+    /// Get the kind of periodic boundary conditions this `System` expects its box vectors
+    /// to describe
+    pub fn pbc_type(&self) -> PbcType {
+        self.pbc_type
+    }
+
+    /// Set the kind of periodic boundary conditions this `System` expects its box vectors
+    /// to describe
+    ///
+    /// This only affects the validation performed by
+    /// [`set_default_periodic_box_vectors()`]; it does not itself change whether any
+    /// `Force` in the `System` uses periodic boundary conditions.
+    ///
+    /// [`set_default_periodic_box_vectors()`]: Self::set_default_periodic_box_vectors()
+    pub fn set_pbc_type(&mut self, pbc_type: PbcType) {
+        self.pbc_type = pbc_type;
+    }
+
+    /// Set the default values of the vectors defining the axes of the periodic box
+    ///
+    /// These vectors are only used for systems that are periodic, and typically defined
+    /// by a [`NonbondedForce`]'s cutoff method. If a Context has already been created, the
+    /// values of its periodic box vectors are unaffected. Instead they are set to the
+    /// values specified in the State used to create it.
+    ///
+    /// The vectors must satisfy certain requirements. `a[1] == a[2] == b[2] == 0`, so `a`
+    /// is parallel to the x axis and `b` lies in the xy-plane. Further, `a[0]`, `b[1]`, and
+    /// `c[2]` must all be positive, and the three vectors must be in a "reduced form" where
+    /// `a[0] >= 2*|b[0]|`, `a[0] >= 2*|c[0]|`, and `b[1] >= 2*|c[1]|`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidBoxVectorsError`] instead of panicking or forwarding to a C++
+    /// exception if the vectors do not satisfy the requirements above. The same requirements
+    /// apply whatever this `System`'s [`PbcType`] is.
+    ///
+    /// [`NonbondedForce`]: crate::force::NonbondedForce
+    pub fn set_default_periodic_box_vectors(
+        &mut self,
+        a: [f64; 3],
+        b: [f64; 3],
+        c: [f64; 3],
+    ) -> Result<(), InvalidBoxVectorsError> {
+        self.pbc_type.validate(a, b, c)?;
+
+        let a = openmm::Vec3 { x: a[0], y: a[1], z: a[2] };
+        let b = openmm::Vec3 { x: b[0], y: b[1], z: b[2] };
+        let c = openmm::Vec3 { x: c[0], y: c[1], z: c[2] };
+
+        // SAFETY: a, b, and c are valid Vec3 values, and the box has already been
+        // validated above, so OpenMM will not throw on this call
+        unsafe { openmm::OpenMM_System_setDefaultPeriodicBoxVectors(self.as_mut(), &a, &b, &c) };
+
+        Ok(())
+    }
+
+    /// Get the default values of the vectors defining the axes of the periodic box
+    pub fn default_periodic_box_vectors(&self) -> [[f64; 3]; 3] {
+        let mut a = openmm::Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+        let mut b = openmm::Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+        let mut c = openmm::Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+
+        // SAFETY: a, b, and c are valid, uniquely-owned Vec3 values for OpenMM to write into
+        unsafe { openmm::OpenMM_System_getDefaultPeriodicBoxVectors(self.as_ref(), &mut a, &mut b, &mut c) };
+
+        [[a.x, a.y, a.z], [b.x, b.y, b.z], [c.x, c.y, c.z]]
+    }
+
+    /// Does this `System` use periodic boundary conditions
+    ///
+    /// This is true if any [`Force`] it contains uses periodic boundary conditions.
+    pub fn uses_periodic_boundary_conditions(&self) -> bool {
+        // SAFETY: OpenMM_System_usesPeriodicBoundaryConditions() does not mutate the target
+        unsafe { openmm::OpenMM_System_usesPeriodicBoundaryConditions(self.as_ref()) != 0 }
+    }
 }
 
 impl Default for System {
@@ -168,4 +330,70 @@ mod tests {
 
         drop(system);
     }
+
+    #[test]
+    fn set_get_default_periodic_box_vectors() {
+        let mut system = System::new();
+
+        system
+            .set_default_periodic_box_vectors([2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0])
+            .unwrap();
+
+        assert_eq!(
+            system.default_periodic_box_vectors(),
+            [[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]
+        );
+    }
+
+    #[test]
+    fn reject_non_reduced_box() {
+        let mut system = System::new();
+
+        let err = system
+            .set_default_periodic_box_vectors([0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0])
+            .unwrap_err();
+        assert_eq!(err, InvalidBoxVectorsError::NonPositiveDiagonal);
+
+        let err = system
+            .set_default_periodic_box_vectors([2.0, 0.0, 0.0], [1.5, 2.0, 0.0], [0.0, 0.0, 2.0])
+            .unwrap_err();
+        assert_eq!(err, InvalidBoxVectorsError::NotTriclinicReduced);
+
+        let err = system
+            .set_default_periodic_box_vectors([2.0, 1.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0])
+            .unwrap_err();
+        assert_eq!(err, InvalidBoxVectorsError::NotReduced);
+    }
+
+    #[test]
+    fn pbc_type_none_still_enforces_openmms_box_invariant() {
+        let mut system = System::new();
+        system.set_pbc_type(PbcType::None);
+
+        let err = system
+            .set_default_periodic_box_vectors([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0])
+            .unwrap_err();
+        assert_eq!(err, InvalidBoxVectorsError::NonPositiveDiagonal);
+
+        system
+            .set_default_periodic_box_vectors([2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0])
+            .unwrap();
+    }
+
+    #[test]
+    fn pbc_type_xy_still_requires_positive_c() {
+        let mut system = System::new();
+        system.set_pbc_type(PbcType::Xy);
+
+        let err = system
+            .set_default_periodic_box_vectors([2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 0.0])
+            .unwrap_err();
+        assert_eq!(err, InvalidBoxVectorsError::NonPositiveDiagonal);
+
+        // Only c's length is semantically meaningful for Xy, but it must still satisfy the
+        // same reduced-form positivity OpenMM requires of every PbcType.
+        system
+            .set_default_periodic_box_vectors([2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 5.0])
+            .unwrap();
+    }
 }
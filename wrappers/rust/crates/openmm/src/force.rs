@@ -1,6 +1,6 @@
 #[allow(unused_imports)]
 use crate::preface::*;
-use openmm_bindings::c_bindings as openmm;
+use crate::sys as openmm;
 use std::os::raw::c_int;
 
 /// Force types apply forces to the particles in a [`System`], or alter their behavior in other ways
@@ -41,7 +41,7 @@ pub trait Force {
     /// Get the force group this `Force` belongs to
     fn group(&self) -> u8 {
         let ptr = self.as_ref() as *const Self::CxxForce;
-        unsafe { openmm::OpenMM_Force_getForceGroup(ptr as *const openmm::OpenMM_Force) as u8 }
+        unsafe { openmm::OpenMM_Force_getForceGroup(ptr as *const openmm::Force) as u8 }
     }
     /// Set the force group this `Force` belongs to
     ///
@@ -52,12 +52,12 @@ pub trait Force {
         }
 
         let ptr = self.as_mut() as *mut Self::CxxForce;
-        unsafe { openmm::OpenMM_Force_setForceGroup(ptr as *mut openmm::OpenMM_Force, group as c_int) };
+        unsafe { openmm::OpenMM_Force_setForceGroup(ptr as *mut openmm::Force, group as c_int) };
     }
     /// Does this `Force` use Periodic Boundary Conditions
     fn uses_pbc(&self) -> bool {
         let ptr = self.as_ref() as *const Self::CxxForce;
-        unsafe { openmm::OpenMM_Force_usesPeriodicBoundaryConditions(ptr as *const openmm::OpenMM_Force) != 0 }
+        unsafe { openmm::OpenMM_Force_usesPeriodicBoundaryConditions(ptr as *const openmm::Force) != 0 }
     }
 
     // fn context_impl(&mut self, context: &mut Context) -> &mut ContextImpl {
@@ -76,3 +76,6 @@ pub trait Force {
 
 pub mod nonbonded;
 pub use nonbonded::NonbondedForce;
+
+pub mod rmsd;
+pub use rmsd::RmsdForce;
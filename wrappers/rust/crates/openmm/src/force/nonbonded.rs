@@ -1,8 +1,12 @@
 #[allow(unused_imports)]
 use crate::preface::*;
-use openmm_bindings::c_bindings as openmm;
+use crate::sys as openmm;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::{CStr, CString};
+use std::fmt;
 use std::ptr::NonNull;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NonbondedMethod {
     /// A non-periodic cutoff scheme with a reaction field
     ///
@@ -47,33 +51,153 @@ impl Default for NonbondedMethod {
     }
 }
 
-impl Into<openmm::OpenMM_NonbondedForce_NonbondedMethod::Type> for NonbondedMethod {
-    fn into(self) -> openmm::OpenMM_NonbondedForce_NonbondedMethod::Type {
+// `OpenMM_NonbondedForce_NonbondedMethod` is generated as a `NewType` (a transparent tuple
+// struct with associated consts for each variant), not the old `ModuleConsts` module-of-`c_int`
+// shape, so the bindgen-side type is the enum itself rather than a nested `::Type` alias, and
+// each variant is reached as an associated const rather than a module item.
+impl Into<openmm::NonbondedForce_NonbondedMethod> for NonbondedMethod {
+    fn into(self) -> openmm::NonbondedForce_NonbondedMethod {
         match self {
-            Self::CutoffNonPeriodic => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_CutoffNonPeriodic,
-            Self::CutoffPeriodic => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_CutoffPeriodic,
-            Self::Ewald => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_Ewald,
-            Self::LjPme => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_LJPME,
-            Self::NoCutoff => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_NoCutoff,
-            Self::Pme => openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_PME,
+            Self::CutoffNonPeriodic => openmm::NonbondedForce_NonbondedMethod::CutoffNonPeriodic,
+            Self::CutoffPeriodic => openmm::NonbondedForce_NonbondedMethod::CutoffPeriodic,
+            Self::Ewald => openmm::NonbondedForce_NonbondedMethod::Ewald,
+            Self::LjPme => openmm::NonbondedForce_NonbondedMethod::LJPME,
+            Self::NoCutoff => openmm::NonbondedForce_NonbondedMethod::NoCutoff,
+            Self::Pme => openmm::NonbondedForce_NonbondedMethod::PME,
         }
     }
 }
 
-impl From<openmm::OpenMM_NonbondedForce_NonbondedMethod::Type> for NonbondedMethod {
-    fn from(method: openmm::OpenMM_NonbondedForce_NonbondedMethod::Type) -> Self {
+impl From<openmm::NonbondedForce_NonbondedMethod> for NonbondedMethod {
+    fn from(method: openmm::NonbondedForce_NonbondedMethod) -> Self {
         match method {
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_CutoffNonPeriodic => Self::CutoffNonPeriodic,
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_CutoffPeriodic => Self::CutoffPeriodic,
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_Ewald => Self::Ewald,
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_LJPME => Self::LjPme,
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_NoCutoff => Self::NoCutoff,
-            openmm::OpenMM_NonbondedForce_NonbondedMethod::OpenMM_NonbondedForce_PME => Self::Pme,
-            i => panic!("{} is not a valid nonbonded method", i),
+            openmm::NonbondedForce_NonbondedMethod::CutoffNonPeriodic => Self::CutoffNonPeriodic,
+            openmm::NonbondedForce_NonbondedMethod::CutoffPeriodic => Self::CutoffPeriodic,
+            openmm::NonbondedForce_NonbondedMethod::Ewald => Self::Ewald,
+            openmm::NonbondedForce_NonbondedMethod::LJPME => Self::LjPme,
+            openmm::NonbondedForce_NonbondedMethod::NoCutoff => Self::NoCutoff,
+            openmm::NonbondedForce_NonbondedMethod::PME => Self::Pme,
+            other => panic!("{} is not a valid nonbonded method", other.0),
         }
     }
 }
 
+/// [`NonbondedForce::use_reaction_field()`] was asked for a reaction-field cutoff distance
+/// that doesn't match the vdW cutoff distance already configured on this force
+///
+/// OpenMM's `NonbondedForce` has only a single cutoff distance, shared between the
+/// Lennard-Jones and Coulomb interactions, so these two distances can't be represented
+/// independently.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MismatchedCutoffError {
+    /// The vdW cutoff distance (nm) already configured via [`NonbondedForce::set_cutoff_distance()`]
+    pub configured_cutoff: f64,
+    /// The reaction-field cutoff distance (nm) passed to [`NonbondedForce::use_reaction_field()`]
+    pub requested_cutoff: f64,
+}
+
+impl fmt::Display for MismatchedCutoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reaction field cutoff {} does not match the vdW cutoff distance {} already configured on this force",
+            self.requested_cutoff, self.configured_cutoff
+        )
+    }
+}
+
+impl std::error::Error for MismatchedCutoffError {}
+
+/// The parameters defining an exception, a particular pair of particles whose Coulomb and
+/// Lennard-Jones interaction is computed using different parameters than those defined for the
+/// individual particles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExceptionParameters {
+    /// The index of the first particle involved in the exception
+    pub particle1: i32,
+    /// The index of the second particle involved in the exception
+    pub particle2: i32,
+    /// The product of the two particles' charges, in units of the proton charge squared
+    pub charge_prod: f64,
+    /// The sigma parameter (in nm) of the Lennard-Jones interaction
+    pub sigma: f64,
+    /// The epsilon parameter (in kJ/mol) of the Lennard-Jones interaction
+    pub epsilon: f64,
+}
+
+/// A named Context parameter defined on a [`NonbondedForce`] via
+/// [`add_global_parameter()`]
+///
+/// Used to identify a global parameter when setting its default value, or when attaching
+/// particle and exception parameter offsets to it with [`add_particle_parameter_offset()`] or
+/// [`add_exception_parameter_offset()`].
+///
+/// [`add_global_parameter()`]: NonbondedForce::add_global_parameter()
+/// [`add_particle_parameter_offset()`]: NonbondedForce::add_particle_parameter_offset()
+/// [`add_exception_parameter_offset()`]: NonbondedForce::add_exception_parameter_offset()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalParameter {
+    index: i32,
+    name: String,
+}
+
+impl GlobalParameter {
+    /// The index of this parameter on the [`NonbondedForce`] that created it
+    pub fn index(&self) -> i32 {
+        self.index
+    }
+
+    /// The name of this parameter
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Build an adjacency list mapping each particle that appears in `bonds` to the particles it is
+/// directly bonded to
+fn build_adjacency(bonds: &[(i32, i32)]) -> HashMap<i32, Vec<i32>> {
+    let mut adjacency: HashMap<i32, Vec<i32>> = HashMap::new();
+    for &(particle1, particle2) in bonds {
+        adjacency.entry(particle1).or_default().push(particle2);
+        adjacency.entry(particle2).or_default().push(particle1);
+    }
+    adjacency
+}
+
+/// Find every pair of particles separated by 1 up to `max_distance` bonds (inclusive)
+///
+/// Returns `(particle1, particle2, bond_distance)` triples with `particle1 < particle2`, each
+/// pair appearing once, tagged with the length of the shortest bonded path between them. Found
+/// by breadth-first search from every particle that appears in `adjacency`.
+fn bonded_pairs_within(adjacency: &HashMap<i32, Vec<i32>>, max_distance: u32) -> Vec<(i32, i32, u32)> {
+    let mut pairs: HashMap<(i32, i32), u32> = HashMap::new();
+
+    for &start in adjacency.keys() {
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut queue = VecDeque::new();
+        queue.push_back((start, 0u32));
+
+        while let Some((particle, distance)) = queue.pop_front() {
+            if distance == max_distance {
+                continue;
+            }
+            for &neighbour in adjacency.get(&particle).into_iter().flatten() {
+                if visited.insert(neighbour) {
+                    let next_distance = distance + 1;
+                    let key = if start < neighbour { (start, neighbour) } else { (neighbour, start) };
+                    pairs.entry(key).or_insert(next_distance);
+                    queue.push_back((neighbour, next_distance));
+                }
+            }
+        }
+    }
+
+    let mut pairs: Vec<(i32, i32, u32)> = pairs.into_iter().map(|((p1, p2), distance)| (p1, p2, distance)).collect();
+    pairs.sort_unstable();
+    pairs
+}
+
 /// Nonbonded LJ and Coulomb forces with the Lorentz-Berthelot combining rule
 ///
 /// This type implements nonbonded interactions between particles, including a Coulomb force to
@@ -138,7 +262,17 @@ impl From<openmm::OpenMM_NonbondedForce_NonbondedMethod::Type> for NonbondedMeth
 ///
 /// [`add_particle()`]: Self::add_particle()
 pub struct NonbondedForce {
-    nbforce_ptr: NonNull<openmm::OpenMM_NonbondedForce>,
+    nbforce_ptr: NonNull<openmm::NonbondedForce>,
+    /// Whether [`set_cutoff_distance()`] has been called explicitly, as opposed to the cutoff
+    /// distance still holding whatever value OpenMM assigned a freshly created force
+    ///
+    /// [`use_reaction_field()`] needs this to tell "the caller configured a vdW cutoff" apart
+    /// from "the cutoff just happens to equal OpenMM's default" — comparing against the default
+    /// value itself would miss a caller who deliberately set that exact value.
+    ///
+    /// [`set_cutoff_distance()`]: Self::set_cutoff_distance()
+    /// [`use_reaction_field()`]: Self::use_reaction_field()
+    cutoff_distance_set: bool,
 }
 
 impl NonbondedForce {
@@ -147,14 +281,14 @@ impl NonbondedForce {
         let ptr = unsafe { openmm::OpenMM_NonbondedForce_create() };
         let nbforce_ptr = NonNull::new(ptr).expect("OpenMM_NonbondedForce returned null pointer");
 
-        Self { nbforce_ptr }
+        Self { nbforce_ptr, cutoff_distance_set: false }
     }
 
-    fn as_ptr(& self) -> *const openmm::OpenMM_NonbondedForce {
+    fn as_ptr(& self) -> *const openmm::NonbondedForce {
         self.nbforce_ptr.as_ptr()
     }
 
-    fn as_mut_ptr(&mut self) -> *mut openmm::OpenMM_NonbondedForce {
+    fn as_mut_ptr(&mut self) -> *mut openmm::NonbondedForce {
         self.nbforce_ptr.as_ptr()
     }
 
@@ -169,6 +303,11 @@ impl NonbondedForce {
         unsafe { openmm::OpenMM_NonbondedForce_addParticle(self.as_mut_ptr(), charge, sigma, epsilon) as i32 }
     }
 
+    /// Get the number of particles for which nonbonded parameters have been defined
+    pub fn num_particles(&self) -> i32 {
+        unsafe { openmm::OpenMM_NonbondedForce_getNumParticles(self.as_ptr()) as i32 }
+    }
+
     /// Get the method used to compute the nonbonded forces
     pub fn method(&self) -> NonbondedMethod {
         unsafe { openmm::OpenMM_NonbondedForce_getNonbondedMethod(self.as_ptr()).into() }
@@ -178,6 +317,398 @@ impl NonbondedForce {
     pub fn set_method(&mut self, method: NonbondedMethod) {
         unsafe { openmm::OpenMM_NonbondedForce_setNonbondedMethod(self.as_mut_ptr(), method.into()) }
     }
+
+    /// Get the cutoff distance (in nm) being used for nonbonded interactions
+    ///
+    /// If the [`NonbondedMethod`] is `NoCutoff`, this value has no effect.
+    pub fn cutoff_distance(&self) -> f64 {
+        unsafe { openmm::OpenMM_NonbondedForce_getCutoffDistance(self.as_ptr()) }
+    }
+
+    /// Set the cutoff distance (in nm) being used for nonbonded interactions
+    ///
+    /// If the [`NonbondedMethod`] is `NoCutoff`, this value has no effect.
+    pub fn set_cutoff_distance(&mut self, distance: f64) {
+        unsafe { openmm::OpenMM_NonbondedForce_setCutoffDistance(self.as_mut_ptr(), distance) };
+        self.cutoff_distance_set = true;
+    }
+
+    /// Get whether a switching function is applied to the Lennard-Jones interaction
+    pub fn use_switching_function(&self) -> bool {
+        unsafe { openmm::OpenMM_NonbondedForce_getUseSwitchingFunction(self.as_ptr()) != 0 }
+    }
+
+    /// Set whether a switching function is applied to the Lennard-Jones interaction
+    ///
+    /// If this is enabled, a switching function is used to smoothly bring the interaction
+    /// to zero over the range [`switching_distance()`], `cutoff_distance()`. This is
+    /// ignored unless a cutoff is being used.
+    ///
+    /// [`switching_distance()`]: Self::switching_distance()
+    pub fn set_use_switching_function(&mut self, use_switching_function: bool) {
+        unsafe {
+            openmm::OpenMM_NonbondedForce_setUseSwitchingFunction(self.as_mut_ptr(), use_switching_function as i32)
+        }
+    }
+
+    /// Get the distance (in nm) at which the switching function begins to reduce the
+    /// Lennard-Jones interaction
+    pub fn switching_distance(&self) -> f64 {
+        unsafe { openmm::OpenMM_NonbondedForce_getSwitchingDistance(self.as_ptr()) }
+    }
+
+    /// Set the distance (in nm) at which the switching function begins to reduce the
+    /// Lennard-Jones interaction
+    ///
+    /// This must be less than the cutoff distance, and is ignored unless
+    /// [`set_use_switching_function()`] has been called with `true`.
+    ///
+    /// [`set_use_switching_function()`]: Self::set_use_switching_function()
+    pub fn set_switching_distance(&mut self, distance: f64) {
+        unsafe { openmm::OpenMM_NonbondedForce_setSwitchingDistance(self.as_mut_ptr(), distance) }
+    }
+
+    /// Get the dielectric constant to use for the solvent in the reaction field approximation
+    pub fn reaction_field_dielectric(&self) -> f64 {
+        unsafe { openmm::OpenMM_NonbondedForce_getReactionFieldDielectric(self.as_ptr()) }
+    }
+
+    /// Set the dielectric constant to use for the solvent in the reaction field approximation
+    ///
+    /// This is only used if the [`NonbondedMethod`] is `CutoffNonPeriodic` or
+    /// `CutoffPeriodic`.
+    pub fn set_reaction_field_dielectric(&mut self, dielectric: f64) {
+        unsafe { openmm::OpenMM_NonbondedForce_setReactionFieldDielectric(self.as_mut_ptr(), dielectric) }
+    }
+
+    /// Get whether a long range dispersion correction is applied to the energy
+    pub fn use_dispersion_correction(&self) -> bool {
+        unsafe { openmm::OpenMM_NonbondedForce_getUseDispersionCorrection(self.as_ptr()) != 0 }
+    }
+
+    /// Set whether to add a contribution to the energy which approximates the effect of all
+    /// Lennard-Jones interactions beyond the cutoff in a periodic system
+    ///
+    /// This is enabled by default and can improve the quality of the result when running a
+    /// simulation at constant pressure.
+    pub fn set_use_dispersion_correction(&mut self, use_dispersion_correction: bool) {
+        unsafe {
+            openmm::OpenMM_NonbondedForce_setUseDispersionCorrection(
+                self.as_mut_ptr(),
+                use_dispersion_correction as i32,
+            )
+        }
+    }
+
+    /// Configure this force to use `CutoffPeriodic` electrostatics with the reaction field
+    /// approximation, in one call
+    ///
+    /// Sets the [`method()`] to `CutoffPeriodic`, the cutoff distance to `cutoff`, and the
+    /// [`reaction_field_dielectric()`] to `dielectric`.
+    ///
+    /// # Errors
+    ///
+    /// OpenMM's `NonbondedForce` uses a single cutoff distance for both the Lennard-Jones
+    /// and Coulomb interactions. If [`set_cutoff_distance()`] was already called explicitly
+    /// with a value other than `cutoff`, calling this method would silently override the vdW
+    /// cutoff the caller configured, so a [`MismatchedCutoffError`] is returned instead.
+    ///
+    /// [`method()`]: Self::method()
+    /// [`reaction_field_dielectric()`]: Self::reaction_field_dielectric()
+    /// [`set_cutoff_distance()`]: Self::set_cutoff_distance()
+    pub fn use_reaction_field(&mut self, cutoff: f64, dielectric: f64) -> Result<(), MismatchedCutoffError> {
+        let configured_cutoff = self.cutoff_distance();
+        if self.cutoff_distance_set && configured_cutoff != cutoff {
+            return Err(MismatchedCutoffError { configured_cutoff, requested_cutoff: cutoff });
+        }
+
+        self.set_method(NonbondedMethod::CutoffPeriodic);
+        self.set_cutoff_distance(cutoff);
+        self.set_reaction_field_dielectric(dielectric);
+
+        Ok(())
+    }
+
+    /// Get the charge, sigma (nm), and epsilon (kJ/mol) parameters of the particle at `index`
+    pub fn particle_parameters(&self, index: i32) -> (f64, f64, f64) {
+        if index < 0 || index >= self.num_particles() {
+            panic!(
+                "Particle index out of bounds: num_particles is {} but the index is {}",
+                self.num_particles(),
+                index
+            )
+        }
+        let mut charge = 0.0;
+        let mut sigma = 0.0;
+        let mut epsilon = 0.0;
+        unsafe {
+            openmm::OpenMM_NonbondedForce_getParticleParameters(self.as_ptr(), index, &mut charge, &mut sigma, &mut epsilon)
+        };
+        (charge, sigma, epsilon)
+    }
+
+    /// Get the number of exceptions that have been defined
+    pub fn num_exceptions(&self) -> i32 {
+        unsafe { openmm::OpenMM_NonbondedForce_getNumExceptions(self.as_ptr()) as i32 }
+    }
+
+    /// Add an interaction exception to the force, overriding the parameters for a single pair
+    /// of particles
+    ///
+    /// Returns the index of the new exception. If `replace` is true and an exception for this
+    /// pair of particles already exists, it is replaced rather than duplicated.
+    pub fn add_exception(
+        &mut self,
+        particle1: i32,
+        particle2: i32,
+        charge_prod: f64,
+        sigma: f64,
+        epsilon: f64,
+        replace: bool,
+    ) -> i32 {
+        unsafe {
+            openmm::OpenMM_NonbondedForce_addException(
+                self.as_mut_ptr(),
+                particle1,
+                particle2,
+                charge_prod,
+                sigma,
+                epsilon,
+                replace as i32,
+            ) as i32
+        }
+    }
+
+    /// Get the parameters of the exception at `index`
+    pub fn exception_parameters(&self, index: i32) -> ExceptionParameters {
+        if index < 0 || index >= self.num_exceptions() {
+            panic!(
+                "Exception index out of bounds: num_exceptions is {} but the index is {}",
+                self.num_exceptions(),
+                index
+            )
+        }
+        let mut particle1 = 0;
+        let mut particle2 = 0;
+        let mut charge_prod = 0.0;
+        let mut sigma = 0.0;
+        let mut epsilon = 0.0;
+        unsafe {
+            openmm::OpenMM_NonbondedForce_getExceptionParameters(
+                self.as_ptr(),
+                index,
+                &mut particle1,
+                &mut particle2,
+                &mut charge_prod,
+                &mut sigma,
+                &mut epsilon,
+            )
+        };
+        ExceptionParameters { particle1, particle2, charge_prod, sigma, epsilon }
+    }
+
+    /// Set the parameters of the exception at `index`
+    ///
+    /// This has no effect on a Context that already exists, unless
+    /// `update_parameters_in_context()` is called.
+    pub fn set_exception_parameters(
+        &mut self,
+        index: i32,
+        particle1: i32,
+        particle2: i32,
+        charge_prod: f64,
+        sigma: f64,
+        epsilon: f64,
+    ) {
+        if index < 0 || index >= self.num_exceptions() {
+            panic!(
+                "Exception index out of bounds: num_exceptions is {} but the index is {}",
+                self.num_exceptions(),
+                index
+            )
+        }
+        unsafe {
+            openmm::OpenMM_NonbondedForce_setExceptionParameters(
+                self.as_mut_ptr(),
+                index,
+                particle1,
+                particle2,
+                charge_prod,
+                sigma,
+                epsilon,
+            )
+        }
+    }
+
+    /// Automatically create exceptions for all pairs of particles separated by 1, 2, or 3 bonds
+    ///
+    /// Many molecular force fields omit Coulomb and Lennard-Jones interactions between particles
+    /// separated by one or two bonds (1-2 and 1-3 pairs), while using scaled-down parameters for
+    /// those separated by three bonds (1-4 pairs). This walks the bond graph built from `bonds`
+    /// with a breadth-first search out to a depth of 3, then calls [`add_exception()`] for each
+    /// pair found: 1-2 and 1-3 pairs are fully excluded (zero charge product and epsilon, with
+    /// sigma set to 1), and 1-4 pairs get
+    /// their charge product scaled by `coulomb14_scale` and their Lennard-Jones epsilon scaled
+    /// by `lj14_scale`, with sigma and the unscaled epsilon combined using the
+    /// Lorentz-Berthelot rule from the parameters already set with [`add_particle()`].
+    ///
+    /// Unlike `OpenMM_NonbondedForce_createExceptionsFromBonds`, the bond-distance search is
+    /// performed in Rust, so the resulting set of exceptions is inspectable and testable from
+    /// the Rust side.
+    ///
+    /// [`add_exception()`]: Self::add_exception()
+    /// [`add_particle()`]: Self::add_particle()
+    pub fn create_exceptions_from_bonds(&mut self, bonds: &[(i32, i32)], coulomb14_scale: f64, lj14_scale: f64) {
+        let adjacency = build_adjacency(bonds);
+
+        for (particle1, particle2, distance) in bonded_pairs_within(&adjacency, 3) {
+            match distance {
+                1 | 2 => {
+                    self.add_exception(particle1, particle2, 0.0, 1.0, 0.0, true);
+                }
+                3 => {
+                    let (charge1, sigma1, epsilon1) = self.particle_parameters(particle1);
+                    let (charge2, sigma2, epsilon2) = self.particle_parameters(particle2);
+
+                    let charge_prod = charge1 * charge2 * coulomb14_scale;
+                    let sigma = 0.5 * (sigma1 + sigma2);
+                    let epsilon = lj14_scale * (epsilon1 * epsilon2).sqrt();
+
+                    self.add_exception(particle1, particle2, charge_prod, sigma, epsilon, true);
+                }
+                _ => unreachable!("bonded_pairs_within(_, 3) cannot return a distance greater than 3"),
+            }
+        }
+    }
+
+    /// Define a new Context parameter that can be used to interpolate particle and exception
+    /// parameters, and return its index
+    ///
+    /// Combine with [`add_particle_parameter_offset()`] or [`add_exception_parameter_offset()`]
+    /// to make particle or exception parameters depend on this Context parameter, for example
+    /// to softcore-decouple a ligand in a free-energy calculation. Call
+    /// [`global_parameter()`] to get a [`GlobalParameter`] handle that identifies this
+    /// parameter to those methods.
+    ///
+    /// [`add_particle_parameter_offset()`]: Self::add_particle_parameter_offset()
+    /// [`add_exception_parameter_offset()`]: Self::add_exception_parameter_offset()
+    /// [`global_parameter()`]: Self::global_parameter()
+    pub fn add_global_parameter(&mut self, name: &str, default_value: f64) -> i32 {
+        let name = CString::new(name).expect("parameter name must not contain a nul byte");
+        unsafe { openmm::OpenMM_NonbondedForce_addGlobalParameter(self.as_mut_ptr(), name.as_ptr(), default_value) as i32 }
+    }
+
+    /// Get the number of global parameters that have been defined
+    pub fn num_global_parameters(&self) -> i32 {
+        unsafe { openmm::OpenMM_NonbondedForce_getNumGlobalParameters(self.as_ptr()) as i32 }
+    }
+
+    /// Get a [`GlobalParameter`] handle identifying the global parameter at `index`
+    pub fn global_parameter(&self, index: i32) -> GlobalParameter {
+        if index < 0 || index >= self.num_global_parameters() {
+            panic!(
+                "Global parameter index out of bounds: num_global_parameters is {} but the index is {}",
+                self.num_global_parameters(),
+                index
+            )
+        }
+        // SAFETY: OpenMM_NonbondedForce_getGlobalParameterName returns a non-null pointer to a
+        // nul-terminated string owned by this force, valid for the duration of this call
+        let name = unsafe {
+            CStr::from_ptr(openmm::OpenMM_NonbondedForce_getGlobalParameterName(self.as_ptr(), index))
+                .to_string_lossy()
+                .into_owned()
+        };
+        GlobalParameter { index, name }
+    }
+
+    /// Set the default value of a global parameter, used by Contexts that have not had a
+    /// different value set explicitly
+    pub fn set_global_parameter_default_value(&mut self, parameter: &GlobalParameter, default_value: f64) {
+        unsafe {
+            openmm::OpenMM_NonbondedForce_setGlobalParameterDefaultValue(self.as_mut_ptr(), parameter.index, default_value)
+        }
+    }
+
+    /// Add a particle parameter offset, making a particle's effective charge, sigma, and
+    /// epsilon depend on the current value of `parameter`
+    ///
+    /// The effective parameters used to compute forces are
+    /// ```text
+    /// charge = base_charge + parameter_value * charge_scale
+    /// sigma = base_sigma + parameter_value * sigma_scale
+    /// epsilon = base_epsilon + parameter_value * epsilon_scale
+    /// ```
+    /// where the "base" values are those set with [`add_particle()`] and `parameter_value` is
+    /// the current value of `parameter` in a Context. Returns the index of the new offset.
+    ///
+    /// [`add_particle()`]: Self::add_particle()
+    pub fn add_particle_parameter_offset(
+        &mut self,
+        parameter: &GlobalParameter,
+        particle_index: i32,
+        charge_scale: f64,
+        sigma_scale: f64,
+        epsilon_scale: f64,
+    ) -> i32 {
+        let name = CString::new(parameter.name.as_str()).expect("parameter name must not contain a nul byte");
+        unsafe {
+            openmm::OpenMM_NonbondedForce_addParticleParameterOffset(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                particle_index,
+                charge_scale,
+                sigma_scale,
+                epsilon_scale,
+            ) as i32
+        }
+    }
+
+    /// Add an exception parameter offset, making an exception's effective charge product,
+    /// sigma, and epsilon depend on the current value of `parameter`
+    ///
+    /// The effective parameters are computed the same way as for
+    /// [`add_particle_parameter_offset()`], but based on the "base" values set with
+    /// [`add_exception()`] or [`set_exception_parameters()`]. Returns the index of the new
+    /// offset.
+    ///
+    /// [`add_particle_parameter_offset()`]: Self::add_particle_parameter_offset()
+    /// [`add_exception()`]: Self::add_exception()
+    /// [`set_exception_parameters()`]: Self::set_exception_parameters()
+    pub fn add_exception_parameter_offset(
+        &mut self,
+        parameter: &GlobalParameter,
+        exception_index: i32,
+        charge_scale: f64,
+        sigma_scale: f64,
+        epsilon_scale: f64,
+    ) -> i32 {
+        let name = CString::new(parameter.name.as_str()).expect("parameter name must not contain a nul byte");
+        unsafe {
+            openmm::OpenMM_NonbondedForce_addExceptionParameterOffset(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                exception_index,
+                charge_scale,
+                sigma_scale,
+                epsilon_scale,
+            ) as i32
+        }
+    }
+
+    /// Recompute this force's parameters in an existing Context
+    ///
+    /// This is much faster than recreating the Context, and should be called after changing
+    /// particle parameters, exception parameters, parameter offsets, or global parameter
+    /// default values whenever a Context using this force already exists.
+    ///
+    /// # Safety
+    ///
+    /// `context` must be a valid, non-null pointer to an `OpenMM_Context` that this force has
+    /// already been added to, directly or via its [`System`].
+    pub unsafe fn update_parameters_in_context(&mut self, context: *mut openmm::Context) {
+        openmm::OpenMM_NonbondedForce_updateParametersInContext(self.as_mut_ptr(), context)
+    }
 }
 
 impl Default for NonbondedForce {
@@ -187,7 +718,7 @@ impl Default for NonbondedForce {
 }
 
 impl Force for NonbondedForce {
-    type CxxForce = openmm::OpenMM_NonbondedForce;
+    type CxxForce = openmm::NonbondedForce;
 
     fn as_ref(&self) -> &Self::CxxForce {
         // SAFETY: self.nbforce_ptr is a non-null pointer to initialised, properly sized memory,
@@ -221,4 +752,156 @@ mod tests {
 
         assert_eq!(force.group(), 16);
     }
+
+    #[test]
+    fn set_get_cutoff_options() {
+        let mut force = NonbondedForce::new();
+
+        force.set_cutoff_distance(1.2);
+        assert_eq!(force.cutoff_distance(), 1.2);
+
+        force.set_use_switching_function(true);
+        assert!(force.use_switching_function());
+
+        force.set_switching_distance(1.0);
+        assert_eq!(force.switching_distance(), 1.0);
+
+        force.set_reaction_field_dielectric(78.5);
+        assert_eq!(force.reaction_field_dielectric(), 78.5);
+
+        force.set_use_dispersion_correction(false);
+        assert!(!force.use_dispersion_correction());
+    }
+
+    #[test]
+    fn use_reaction_field_sets_method_and_cutoff() {
+        let mut force = NonbondedForce::new();
+
+        force.use_reaction_field(1.4, 78.5).unwrap();
+
+        assert_eq!(force.method(), NonbondedMethod::CutoffPeriodic);
+        assert_eq!(force.cutoff_distance(), 1.4);
+        assert_eq!(force.reaction_field_dielectric(), 78.5);
+    }
+
+    #[test]
+    fn use_reaction_field_rejects_mismatched_cutoff() {
+        let mut force = NonbondedForce::new();
+        force.set_cutoff_distance(0.9);
+
+        let err = force.use_reaction_field(1.4, 78.5).unwrap_err();
+
+        assert_eq!(err.configured_cutoff, 0.9);
+        assert_eq!(err.requested_cutoff, 1.4);
+    }
+
+    #[test]
+    fn use_reaction_field_rejects_mismatch_at_openmm_default_cutoff() {
+        // 1.0 happens to be the cutoff distance OpenMM assigns to a fresh NonbondedForce, but
+        // it's also a perfectly ordinary vdW cutoff a caller might set explicitly. Setting it
+        // explicitly must still be caught as a mismatch rather than silently overridden.
+        let mut force = NonbondedForce::new();
+        force.set_cutoff_distance(1.0);
+
+        let err = force.use_reaction_field(1.4, 78.5).unwrap_err();
+
+        assert_eq!(err.configured_cutoff, 1.0);
+        assert_eq!(err.requested_cutoff, 1.4);
+    }
+
+    #[test]
+    fn use_reaction_field_allows_untouched_default_cutoff() {
+        // Without an explicit set_cutoff_distance() call, use_reaction_field() is free to pick
+        // its own cutoff even though it happens to differ from OpenMM's untouched default.
+        let mut force = NonbondedForce::new();
+
+        force.use_reaction_field(1.4, 78.5).unwrap();
+
+        assert_eq!(force.cutoff_distance(), 1.4);
+    }
+
+    #[test]
+    fn add_get_set_exception() {
+        let mut force = NonbondedForce::new();
+        force.add_particle(1.0, 0.3, 0.5);
+        force.add_particle(-1.0, 0.3, 0.5);
+
+        let index = force.add_exception(0, 1, 0.0, 1.0, 0.0, false);
+        assert_eq!(force.num_exceptions(), 1);
+        assert_eq!(
+            force.exception_parameters(index),
+            ExceptionParameters { particle1: 0, particle2: 1, charge_prod: 0.0, sigma: 1.0, epsilon: 0.0 }
+        );
+
+        force.set_exception_parameters(index, 0, 1, -1.0, 0.3, 0.5);
+        assert_eq!(
+            force.exception_parameters(index),
+            ExceptionParameters { particle1: 0, particle2: 1, charge_prod: -1.0, sigma: 0.3, epsilon: 0.5 }
+        );
+    }
+
+    #[test]
+    fn bonded_pairs_within_finds_1_2_3_neighbours() {
+        // A linear chain 0-1-2-3-4
+        let adjacency = build_adjacency(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let pairs = bonded_pairs_within(&adjacency, 3);
+
+        assert_eq!(
+            pairs,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (0, 3, 3),
+                (1, 2, 1),
+                (1, 3, 2),
+                (1, 4, 3),
+                (2, 3, 1),
+                (2, 4, 2),
+                (3, 4, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_exceptions_from_bonds_scales_1_4_pairs() {
+        let mut force = NonbondedForce::new();
+        force.add_particle(1.0, 0.2, 0.4);
+        force.add_particle(1.0, 0.3, 0.6);
+        force.add_particle(1.0, 0.3, 0.6);
+        force.add_particle(1.0, 0.4, 0.8);
+
+        force.create_exceptions_from_bonds(&[(0, 1), (1, 2), (2, 3)], 0.5, 0.5);
+
+        assert_eq!(force.num_exceptions(), 6);
+
+        let one_four = force.exception_parameters(2);
+        assert_eq!(one_four.particle1, 0);
+        assert_eq!(one_four.particle2, 3);
+        assert_eq!(one_four.charge_prod, 0.5);
+        assert_eq!(one_four.sigma, 0.5 * (0.2 + 0.4));
+        assert_eq!(one_four.epsilon, 0.5 * (0.4f64 * 0.8).sqrt());
+
+        let one_two = force.exception_parameters(0);
+        assert_eq!(one_two.charge_prod, 0.0);
+        assert_eq!(one_two.epsilon, 0.0);
+    }
+
+    #[test]
+    fn add_global_parameter_and_offsets() {
+        let mut force = NonbondedForce::new();
+        force.add_particle(1.0, 0.3, 0.5);
+        force.add_particle(-1.0, 0.3, 0.5);
+        let exception = force.add_exception(0, 1, -1.0, 0.3, 0.5, false);
+
+        let lambda_index = force.add_global_parameter("lambda_electrostatics", 1.0);
+        assert_eq!(lambda_index, 0);
+
+        let lambda = force.global_parameter(lambda_index);
+        assert_eq!(lambda.name(), "lambda_electrostatics");
+
+        force.set_global_parameter_default_value(&lambda, 0.5);
+
+        force.add_particle_parameter_offset(&lambda, 0, -1.0, 0.0, 0.0);
+        force.add_exception_parameter_offset(&lambda, exception, -1.0, 0.0, 0.0);
+    }
 }
@@ -0,0 +1,297 @@
+#[allow(unused_imports)]
+use crate::preface::*;
+use crate::sys as openmm;
+use std::collections::HashSet;
+use std::fmt;
+use std::ptr::NonNull;
+
+/// The reference positions or particle subset passed to [`RmsdForce`] are not valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RmsdForceError {
+    /// A particle index is negative, or is not less than the number of reference positions
+    IllegalParticle(i32),
+    /// A particle index appears more than once in the particle subset
+    DuplicateParticle(i32),
+}
+
+impl fmt::Display for RmsdForceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IllegalParticle(p) => write!(f, "particle index {} is not a valid reference position index", p),
+            Self::DuplicateParticle(p) => write!(f, "particle index {} appears more than once", p),
+        }
+    }
+}
+
+impl std::error::Error for RmsdForceError {}
+
+/// Check that every particle index is unique and less than `num_reference_positions`
+fn validate_particles(particles: &[i32], num_reference_positions: usize) -> Result<(), RmsdForceError> {
+    let mut seen = HashSet::with_capacity(particles.len());
+    for &particle in particles {
+        if particle < 0 || particle as usize >= num_reference_positions {
+            return Err(RmsdForceError::IllegalParticle(particle));
+        }
+        if !seen.insert(particle) {
+            return Err(RmsdForceError::DuplicateParticle(particle));
+        }
+    }
+    Ok(())
+}
+
+/// Build a new, temporary `OpenMM_Vec3Array` holding `values`
+///
+/// The caller is responsible for destroying the returned array once it has been handed to
+/// OpenMM, which copies its contents rather than taking ownership of it.
+fn vec3_array_from_slice(values: &[[f64; 3]]) -> NonNull<openmm::Vec3Array> {
+    // SAFETY: OpenMM_Vec3Array_create() returns a pointer to a new, empty C++ array
+    let array = unsafe { openmm::OpenMM_Vec3Array_create(0) };
+    let mut array = NonNull::new(array).expect("OpenMM_Vec3Array_create returned null pointer");
+
+    for value in values {
+        let vec3 = openmm::Vec3 { x: value[0], y: value[1], z: value[2] };
+        // SAFETY: array is a unique, non-null pointer to an initialized OpenMM_Vec3Array
+        unsafe { openmm::OpenMM_Vec3Array_append(array.as_mut(), &vec3) };
+    }
+
+    array
+}
+
+/// Read the contents of an `OpenMM_Vec3Array` into a `Vec`
+///
+/// # Safety
+///
+/// `array` must be a valid, non-null pointer to an initialized `OpenMM_Vec3Array`.
+unsafe fn vec3_array_to_vec(array: *const openmm::Vec3Array) -> Vec<[f64; 3]> {
+    let len = openmm::OpenMM_Vec3Array_getSize(array);
+    (0..len)
+        .map(|i| {
+            let v = *openmm::OpenMM_Vec3Array_get(array, i);
+            [v.x, v.y, v.z]
+        })
+        .collect()
+}
+
+/// Build a new, temporary `OpenMM_IntArray` holding `values`
+///
+/// The caller is responsible for destroying the returned array once it has been handed to
+/// OpenMM, which copies its contents rather than taking ownership of it.
+fn int_array_from_slice(values: &[i32]) -> NonNull<openmm::IntArray> {
+    // SAFETY: OpenMM_IntArray_create() returns a pointer to a new, empty C++ array
+    let array = unsafe { openmm::OpenMM_IntArray_create(0) };
+    let mut array = NonNull::new(array).expect("OpenMM_IntArray_create returned null pointer");
+
+    for &value in values {
+        // SAFETY: array is a unique, non-null pointer to an initialized OpenMM_IntArray
+        unsafe { openmm::OpenMM_IntArray_append(array.as_mut(), value) };
+    }
+
+    array
+}
+
+/// Read the contents of an `OpenMM_IntArray` into a `Vec`
+///
+/// # Safety
+///
+/// `array` must be a valid, non-null pointer to an initialized `OpenMM_IntArray`.
+unsafe fn int_array_to_vec(array: *const openmm::IntArray) -> Vec<i32> {
+    let len = openmm::OpenMM_IntArray_getSize(array);
+    (0..len).map(|i| openmm::OpenMM_IntArray_get(array, i)).collect()
+}
+
+/// A restraint force based on the root-mean-squared deviation (RMSD) from a set of reference
+/// positions
+///
+/// To use this type, create an `RmsdForce` object, then add it to a [`System`] with
+/// [`System::add_force()`]. This class computes the RMSD of the current particle positions from
+/// a reference set, optionally restricted to a subset of particles, and applies a force along
+/// the gradient of that RMSD. It is typically used as a collective variable for biasing
+/// simulations — for example through `CustomCVForce` — rather than as a restraint on its own.
+///
+/// [`System::add_force()`]: crate::core::System::add_force()
+pub struct RmsdForce {
+    ptr: NonNull<openmm::RMSDForce>,
+}
+
+impl RmsdForce {
+    /// Create a new `RmsdForce` from a set of reference positions (in nm) and an optional
+    /// subset of particle indices to include in the RMSD calculation
+    ///
+    /// If `particles` is `None` or empty, every particle is included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`RmsdForceError`] instead of letting the underlying C++ throw if a particle
+    /// index is out of range for `reference_positions`, or a particle index is repeated.
+    pub fn new(reference_positions: &[[f64; 3]], particles: Option<&[i32]>) -> Result<Self, RmsdForceError> {
+        let particles = particles.unwrap_or(&[]);
+        validate_particles(particles, reference_positions.len())?;
+
+        let mut reference_array = vec3_array_from_slice(reference_positions);
+        let mut particle_array = int_array_from_slice(particles);
+
+        // SAFETY: reference_array and particle_array are valid, non-null pointers to
+        // initialized arrays; OpenMM_RMSDForce_create copies their contents
+        let ptr = unsafe { openmm::OpenMM_RMSDForce_create(reference_array.as_mut(), particle_array.as_mut()) };
+
+        // SAFETY: the arrays are no longer needed once the force has copied their contents
+        unsafe {
+            openmm::OpenMM_Vec3Array_destroy(reference_array.as_mut());
+            openmm::OpenMM_IntArray_destroy(particle_array.as_mut());
+        }
+
+        let ptr = NonNull::new(ptr).expect("OpenMM_RMSDForce_create returned null pointer");
+
+        Ok(Self { ptr })
+    }
+
+    fn as_ptr(&self) -> *const openmm::RMSDForce {
+        self.ptr.as_ptr()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut openmm::RMSDForce {
+        self.ptr.as_ptr()
+    }
+
+    /// Get the reference positions (in nm) used for the RMSD calculation
+    pub fn reference_positions(&self) -> Vec<[f64; 3]> {
+        // SAFETY: OpenMM_RMSDForce_getReferencePositions() returns a non-null pointer to an
+        // array owned by this force, which outlives the borrow
+        unsafe { vec3_array_to_vec(openmm::OpenMM_RMSDForce_getReferencePositions(self.as_ptr())) }
+    }
+
+    /// Set the reference positions (in nm) used for the RMSD calculation
+    ///
+    /// This has no effect on a Context that already exists, unless
+    /// [`update_parameters_in_context()`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`RmsdForceError`] if the currently configured particle subset would no
+    /// longer be in range for `reference_positions`.
+    ///
+    /// [`update_parameters_in_context()`]: Self::update_parameters_in_context()
+    pub fn set_reference_positions(&mut self, reference_positions: &[[f64; 3]]) -> Result<(), RmsdForceError> {
+        validate_particles(&self.particles(), reference_positions.len())?;
+
+        let mut reference_array = vec3_array_from_slice(reference_positions);
+        // SAFETY: reference_array is a valid, non-null pointer to an initialized array, and
+        // OpenMM_RMSDForce_setReferencePositions copies its contents
+        unsafe { openmm::OpenMM_RMSDForce_setReferencePositions(self.as_mut_ptr(), reference_array.as_mut()) };
+        // SAFETY: the array is no longer needed once the force has copied its contents
+        unsafe { openmm::OpenMM_Vec3Array_destroy(reference_array.as_mut()) };
+
+        Ok(())
+    }
+
+    /// Get the indices of the particles included in the RMSD calculation
+    ///
+    /// An empty list means every particle in the `System` is included.
+    pub fn particles(&self) -> Vec<i32> {
+        // SAFETY: OpenMM_RMSDForce_getParticles() returns a non-null pointer to an array
+        // owned by this force, which outlives the borrow
+        unsafe { int_array_to_vec(openmm::OpenMM_RMSDForce_getParticles(self.as_ptr())) }
+    }
+
+    /// Set the indices of the particles included in the RMSD calculation
+    ///
+    /// An empty list means every particle in the `System` is included. This has no effect on a
+    /// Context that already exists, unless [`update_parameters_in_context()`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`RmsdForceError`] instead of letting the underlying C++ throw if a particle
+    /// index is out of range for the current reference positions, or a particle index is
+    /// repeated.
+    ///
+    /// [`update_parameters_in_context()`]: Self::update_parameters_in_context()
+    pub fn set_particles(&mut self, particles: &[i32]) -> Result<(), RmsdForceError> {
+        validate_particles(particles, self.reference_positions().len())?;
+
+        let mut particle_array = int_array_from_slice(particles);
+        // SAFETY: particle_array is a valid, non-null pointer to an initialized array, and
+        // OpenMM_RMSDForce_setParticles copies its contents
+        unsafe { openmm::OpenMM_RMSDForce_setParticles(self.as_mut_ptr(), particle_array.as_mut()) };
+        // SAFETY: the array is no longer needed once the force has copied its contents
+        unsafe { openmm::OpenMM_IntArray_destroy(particle_array.as_mut()) };
+
+        Ok(())
+    }
+
+    /// Recompute this force's reference positions and particle subset in an existing Context
+    ///
+    /// This is much faster than recreating the Context, and should be called after
+    /// [`set_reference_positions()`] or [`set_particles()`] whenever a Context using this force
+    /// already exists.
+    ///
+    /// # Safety
+    ///
+    /// `context` must be a valid, non-null pointer to an `OpenMM_Context` that this force has
+    /// already been added to, directly or via its [`System`].
+    ///
+    /// [`set_reference_positions()`]: Self::set_reference_positions()
+    /// [`set_particles()`]: Self::set_particles()
+    pub unsafe fn update_parameters_in_context(&mut self, context: *mut openmm::Context) {
+        openmm::OpenMM_RMSDForce_updateParametersInContext(self.as_mut_ptr(), context)
+    }
+}
+
+impl Force for RmsdForce {
+    type CxxForce = openmm::RMSDForce;
+
+    fn as_ref(&self) -> &Self::CxxForce {
+        // SAFETY: self.ptr is a non-null pointer to initialised, properly sized memory,
+        // and we are immutably borrowing it
+        unsafe { self.ptr.as_ref() }
+    }
+
+    fn as_mut(&mut self) -> &mut Self::CxxForce {
+        // SAFETY: self.ptr is a non-null pointer to initialised, properly sized memory,
+        // and we are mutably borrowing it
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_force() {
+        let _force = RmsdForce::new(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]], None).unwrap();
+    }
+
+    #[test]
+    fn reject_illegal_particle() {
+        let err = RmsdForce::new(&[[0.0, 0.0, 0.0]], Some(&[1])).unwrap_err();
+        assert_eq!(err, RmsdForceError::IllegalParticle(1));
+    }
+
+    #[test]
+    fn reject_duplicate_particle() {
+        let err = RmsdForce::new(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]], Some(&[0, 0])).unwrap_err();
+        assert_eq!(err, RmsdForceError::DuplicateParticle(0));
+    }
+
+    #[test]
+    fn set_get_reference_positions_and_particles() {
+        let mut force = RmsdForce::new(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]], Some(&[0, 1])).unwrap();
+
+        force.set_reference_positions(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]).unwrap();
+        assert_eq!(force.reference_positions(), vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+
+        force.set_particles(&[1]).unwrap();
+        assert_eq!(force.particles(), vec![1]);
+    }
+
+    #[test]
+    fn test_force_group() {
+        let mut force = RmsdForce::new(&[[0.0, 0.0, 0.0]], None).unwrap();
+
+        assert_eq!(force.group(), 0);
+
+        force.set_group(4);
+
+        assert_eq!(force.group(), 4);
+    }
+}
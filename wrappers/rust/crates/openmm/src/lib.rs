@@ -5,6 +5,21 @@ pub mod preface {
 #[allow(unused_imports)]
 use crate::preface::*;
 
+/// The low-level binding set the safe API in this crate is built against
+///
+/// This crate's safe wrappers call `OpenMM_*` C functions directly (`OpenMM_System_create`,
+/// `OpenMM_NonbondedForce_addParticle`, ...), so they can only be built against
+/// `openmm_bindings::c_bindings`: `cpp_bindings` exposes the raw C++ API as namespaced methods on
+/// opaque types rather than as free functions, and isn't call-compatible with this module. Making
+/// [`core`] and [`force`] build against either binding set would mean writing every call site
+/// twice, so `sys` stays hard-wired to `c_bindings` — the `cpp-api` feature only adds raw access
+/// to `openmm_bindings::cpp_bindings` alongside this safe layer, it does not replace what backs it.
+#[cfg(feature = "c-api")]
+pub(crate) use openmm_bindings::c_bindings as sys;
+
+#[cfg(not(feature = "c-api"))]
+compile_error!("the `openmm` crate's safe API requires the `c-api` feature of `openmm-bindings`");
+
 pub mod core;
 
 pub mod force;